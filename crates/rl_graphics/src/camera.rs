@@ -0,0 +1,156 @@
+//! A free-fly camera driven by the event loop: WASD to move, mouse-look to
+//! turn, scroll to adjust speed, with an optional cursor-capture toggle.
+
+use std::f32::consts::FRAC_PI_2;
+
+use winit::{
+    event::{ElementState, MouseScrollDelta},
+    keyboard::{Key, NamedKey},
+};
+
+const SAFE_FRAC_PI_2: f32 = FRAC_PI_2 - 0.0001;
+
+/// Position, orientation, and lens of a perspective camera.
+pub struct Camera {
+    pub position: nalgebra::Point3<f32>,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fovy: f32,
+    pub znear: f32,
+    pub zfar: f32,
+}
+
+impl Camera {
+    pub fn new(position: nalgebra::Point3<f32>, yaw: f32, pitch: f32) -> Self {
+        Self {
+            position,
+            yaw,
+            pitch,
+            fovy: std::f32::consts::FRAC_PI_4,
+            znear: 0.1,
+            zfar: 1000.0,
+        }
+    }
+
+    fn view_matrix(&self) -> nalgebra::Matrix4<f32> {
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let forward =
+            nalgebra::Vector3::new(cos_pitch * cos_yaw, cos_pitch * sin_yaw, sin_pitch).normalize();
+        nalgebra::Matrix4::look_at_rh(
+            &self.position,
+            &(self.position + forward),
+            &nalgebra::Vector3::z(),
+        )
+    }
+
+    fn projection_matrix(&self, aspect_ratio: f32) -> nalgebra::Matrix4<f32> {
+        nalgebra::Perspective3::new(aspect_ratio, self.fovy, self.znear, self.zfar).into_inner()
+    }
+
+    pub fn view_projection(&self, aspect_ratio: f32) -> nalgebra::Matrix4<f32> {
+        self.projection_matrix(aspect_ratio) * self.view_matrix()
+    }
+}
+
+/// Consumes keyboard, mouse-motion, and scroll events to update a [`Camera`]
+/// each frame. Speed and sensitivity are configurable; `capture_cursor`
+/// tracks whether mouse-look is currently active.
+pub struct CameraController {
+    pub speed: f32,
+    pub sensitivity: f32,
+    pub capture_cursor: bool,
+
+    amount_forward: f32,
+    amount_backward: f32,
+    amount_left: f32,
+    amount_right: f32,
+    amount_up: f32,
+    amount_down: f32,
+    rotate_horizontal: f32,
+    rotate_vertical: f32,
+    scroll: f32,
+}
+
+impl CameraController {
+    pub fn new(speed: f32, sensitivity: f32) -> Self {
+        Self {
+            speed,
+            sensitivity,
+            capture_cursor: false,
+            amount_forward: 0.0,
+            amount_backward: 0.0,
+            amount_left: 0.0,
+            amount_right: 0.0,
+            amount_up: 0.0,
+            amount_down: 0.0,
+            rotate_horizontal: 0.0,
+            rotate_vertical: 0.0,
+            scroll: 0.0,
+        }
+    }
+
+    /// Returns `true` if `key` was one this controller handles, so callers
+    /// can decide whether to fall through to other keybindings.
+    pub fn process_keyboard(&mut self, key: &Key, state: ElementState) -> bool {
+        let amount = if state == ElementState::Pressed {
+            1.0
+        } else {
+            0.0
+        };
+        match key {
+            Key::Character(character) if character.eq_ignore_ascii_case("w") => {
+                self.amount_forward = amount;
+            }
+            Key::Character(character) if character.eq_ignore_ascii_case("s") => {
+                self.amount_backward = amount;
+            }
+            Key::Character(character) if character.eq_ignore_ascii_case("a") => {
+                self.amount_left = amount;
+            }
+            Key::Character(character) if character.eq_ignore_ascii_case("d") => {
+                self.amount_right = amount;
+            }
+            Key::Named(NamedKey::Space) => self.amount_up = amount,
+            Key::Named(NamedKey::Shift) => self.amount_down = amount,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn process_mouse(&mut self, delta_x: f64, delta_y: f64) {
+        if self.capture_cursor {
+            self.rotate_horizontal += delta_x as f32;
+            self.rotate_vertical += delta_y as f32;
+        }
+    }
+
+    pub fn process_scroll(&mut self, delta: &MouseScrollDelta) {
+        self.scroll += match delta {
+            MouseScrollDelta::LineDelta(_, scroll) => *scroll,
+            MouseScrollDelta::PixelDelta(position) => position.y as f32,
+        };
+    }
+
+    pub fn update_camera(&mut self, camera: &mut Camera, dt: f32) {
+        let (sin_yaw, cos_yaw) = camera.yaw.sin_cos();
+        let forward = nalgebra::Vector3::new(cos_yaw, sin_yaw, 0.0);
+        let right = nalgebra::Vector3::new(-sin_yaw, cos_yaw, 0.0);
+
+        camera.position +=
+            forward * (self.amount_forward - self.amount_backward) * self.speed * dt;
+        camera.position += right * (self.amount_right - self.amount_left) * self.speed * dt;
+        camera.position.z += (self.amount_up - self.amount_down) * self.speed * dt;
+
+        camera.yaw += self.rotate_horizontal.to_radians() * self.sensitivity * dt;
+        camera.pitch -= self.rotate_vertical.to_radians() * self.sensitivity * dt;
+        camera.pitch = camera.pitch.clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2);
+
+        self.rotate_horizontal = 0.0;
+        self.rotate_vertical = 0.0;
+
+        let speed_multiplier = (1.0 + self.scroll * 0.1).max(0.1);
+        self.speed *= speed_multiplier;
+        self.scroll = 0.0;
+    }
+}
@@ -0,0 +1,54 @@
+//! Compute-pass support alongside the render graph: dispatch compute
+//! shaders into the same frame's encoder ahead of the color pass, e.g. for
+//! particle simulation or GPU-side vertex generation writing into a
+//! `STORAGE | VERTEX` buffer a `Renderable` then draws.
+
+pub trait Computable {
+    fn compute(&mut self, cpass: &mut wgpu::ComputePass<'_>, queue: &wgpu::Queue);
+}
+
+/// A compute shader's pipeline and the bind group layout it was built
+/// against, so callers can create matching bind groups for their buffers.
+pub struct ComputePipeline {
+    pub pipeline: wgpu::ComputePipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+}
+
+impl ComputePipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        shader_source: &str,
+        entry_point: &str,
+        bind_group_layout_entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(label),
+            entries: bind_group_layout_entries,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point,
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+        }
+    }
+}
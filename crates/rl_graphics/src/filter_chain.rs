@@ -0,0 +1,581 @@
+//! RetroArch-style post-processing: render the scene offscreen, then run an
+//! ordered chain of full-screen shader passes (CRT, bloom, FXAA, ...) before
+//! presenting, so users can stack effects without touching their
+//! `Renderable`s.
+
+use std::{error::Error, fmt, fs, path::Path};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::render_graph::{DepthTarget, Pass, SlotTexture};
+
+pub const SCENE_COLOR_SLOT: &str = "scene_color";
+
+/// Where a filter pass reads its input texture from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterSource {
+    /// The untouched scene render, i.e. the `scene_color` slot.
+    Original,
+    /// The previous pass's output (or the scene render, for the first pass).
+    Previous,
+}
+
+/// One entry of a parsed filter preset.
+#[derive(Clone, Debug)]
+pub struct FilterPresetPass {
+    pub shader_path: String,
+    pub scale: f32,
+    pub filter_mode: wgpu::FilterMode,
+    pub source: FilterSource,
+}
+
+#[derive(Debug)]
+pub enum PresetError {
+    Io(std::io::Error),
+    MissingKey { pass: usize, key: &'static str },
+    InvalidValue { key: String, value: String },
+    /// `passes = 0` (or no `passes` key at all): a chain with no passes would
+    /// never write to the swapchain, leaving whatever was already there on
+    /// screen. Use a one-pass passthrough preset instead.
+    EmptyChain,
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Io(err) => write!(f, "failed to read preset: {err}"),
+            PresetError::MissingKey { pass, key } => {
+                write!(f, "preset pass {pass} is missing '{key}'")
+            }
+            PresetError::InvalidValue { key, value } => {
+                write!(f, "preset key '{key}' has invalid value '{value}'")
+            }
+            PresetError::EmptyChain => {
+                write!(f, "preset declares zero passes, which never writes a frame")
+            }
+        }
+    }
+}
+
+impl Error for PresetError {}
+
+impl From<std::io::Error> for PresetError {
+    fn from(err: std::io::Error) -> Self {
+        PresetError::Io(err)
+    }
+}
+
+/// Parses a RetroArch-style preset: a `passes = N` line followed by
+/// `shaderN`, `scaleN`, `filterN`, and `sourceN` keys for each of the `N`
+/// passes, e.g.
+///
+/// ```text
+/// passes = 2
+/// shader0 = shaders/crt.wgsl
+/// scale0 = 1.0
+/// filter0 = linear
+/// source0 = original
+/// shader1 = shaders/fxaa.wgsl
+/// scale1 = 1.0
+/// filter1 = linear
+/// source1 = previous
+/// ```
+pub fn parse_preset(text: &str) -> Result<Vec<FilterPresetPass>, PresetError> {
+    let mut values = std::collections::HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            values.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let pass_count: usize = values
+        .get("passes")
+        .map(|value| value.parse())
+        .transpose()
+        .map_err(|_| PresetError::InvalidValue {
+            key: "passes".to_string(),
+            value: values.get("passes").cloned().unwrap_or_default(),
+        })?
+        .unwrap_or(0);
+
+    if pass_count == 0 {
+        return Err(PresetError::EmptyChain);
+    }
+
+    let mut passes = Vec::with_capacity(pass_count);
+    for index in 0..pass_count {
+        let shader_path = values
+            .get(&format!("shader{index}"))
+            .cloned()
+            .ok_or(PresetError::MissingKey {
+                pass: index,
+                key: "shader",
+            })?;
+
+        let scale = values
+            .get(&format!("scale{index}"))
+            .map(|value| value.parse::<f32>())
+            .transpose()
+            .map_err(|_| PresetError::InvalidValue {
+                key: format!("scale{index}"),
+                value: values.get(&format!("scale{index}")).cloned().unwrap(),
+            })?
+            .unwrap_or(1.0);
+
+        let filter_mode = match values.get(&format!("filter{index}")).map(String::as_str) {
+            Some("nearest") => wgpu::FilterMode::Nearest,
+            Some("linear") | None => wgpu::FilterMode::Linear,
+            Some(other) => {
+                return Err(PresetError::InvalidValue {
+                    key: format!("filter{index}"),
+                    value: other.to_string(),
+                })
+            }
+        };
+
+        let source = match values.get(&format!("source{index}")).map(String::as_str) {
+            Some("original") => FilterSource::Original,
+            Some("previous") | None => FilterSource::Previous,
+            Some(other) => {
+                return Err(PresetError::InvalidValue {
+                    key: format!("source{index}"),
+                    value: other.to_string(),
+                })
+            }
+        };
+
+        passes.push(FilterPresetPass {
+            shader_path,
+            scale,
+            filter_mode,
+            source,
+        });
+    }
+
+    Ok(passes)
+}
+
+pub fn load_preset(path: impl AsRef<Path>) -> Result<Vec<FilterPresetPass>, PresetError> {
+    let text = fs::read_to_string(path)?;
+    parse_preset(&text)
+}
+
+#[cfg(test)]
+mod preset_tests {
+    use super::*;
+
+    #[test]
+    fn parses_two_passes() {
+        let preset = parse_preset(
+            "passes = 2\n\
+             shader0 = shaders/crt.wgsl\n\
+             scale0 = 1.0\n\
+             filter0 = linear\n\
+             source0 = original\n\
+             shader1 = shaders/fxaa.wgsl\n\
+             scale1 = 0.5\n\
+             filter1 = nearest\n\
+             source1 = previous\n",
+        )
+        .expect("well-formed preset parses");
+
+        assert_eq!(preset.len(), 2);
+        assert_eq!(preset[0].shader_path, "shaders/crt.wgsl");
+        assert_eq!(preset[0].scale, 1.0);
+        assert_eq!(preset[0].filter_mode, wgpu::FilterMode::Linear);
+        assert_eq!(preset[0].source, FilterSource::Original);
+        assert_eq!(preset[1].shader_path, "shaders/fxaa.wgsl");
+        assert_eq!(preset[1].scale, 0.5);
+        assert_eq!(preset[1].filter_mode, wgpu::FilterMode::Nearest);
+        assert_eq!(preset[1].source, FilterSource::Previous);
+    }
+
+    #[test]
+    fn rejects_non_numeric_passes() {
+        let err = parse_preset("passes = many\n").unwrap_err();
+        assert!(matches!(
+            err,
+            PresetError::InvalidValue { key, .. } if key == "passes"
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_shader_key() {
+        let err = parse_preset("passes = 1\nscale0 = 1.0\n").unwrap_err();
+        assert!(matches!(
+            err,
+            PresetError::MissingKey { pass: 0, key: "shader" }
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_filter_mode() {
+        let err = parse_preset("passes = 1\nshader0 = x.wgsl\nfilter0 = blurry\n").unwrap_err();
+        assert!(matches!(
+            err,
+            PresetError::InvalidValue { key, value }
+                if key == "filter0" && value == "blurry"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_source() {
+        let err = parse_preset("passes = 1\nshader0 = x.wgsl\nsource0 = sideways\n").unwrap_err();
+        assert!(matches!(
+            err,
+            PresetError::InvalidValue { key, value }
+                if key == "source0" && value == "sideways"
+        ));
+    }
+
+    #[test]
+    fn rejects_zero_passes() {
+        assert!(matches!(
+            parse_preset("passes = 0\n"),
+            Err(PresetError::EmptyChain)
+        ));
+        assert!(matches!(parse_preset(""), Err(PresetError::EmptyChain)));
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct FilterUniforms {
+    output_size: [f32; 2],
+    source_size: [f32; 2],
+    frame_count: u32,
+    _padding: [u32; 3],
+}
+
+struct FilterPass {
+    scale: f32,
+    source: FilterSource,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buf: wgpu::Buffer,
+    target: wgpu::Texture,
+    target_view: wgpu::TextureView,
+}
+
+impl FilterPass {
+    fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        preset: &FilterPresetPass,
+        shader_source: &str,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(&preset.shader_path),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("FilterChain pass sampler"),
+            mag_filter: preset.filter_mode,
+            min_filter: preset.filter_mode,
+            ..Default::default()
+        });
+
+        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("FilterChain pass uniforms"),
+            size: std::mem::size_of::<FilterUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("FilterChain pass bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("FilterChain pass pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("FilterChain pass pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(surface_format.into())],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let target = Self::make_target(
+            device,
+            surface_format,
+            scaled_dimension(width, preset.scale),
+            scaled_dimension(height, preset.scale),
+        );
+        let target_view = target.create_view(&Default::default());
+
+        Self {
+            scale: preset.scale,
+            source: preset.source,
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buf,
+            target,
+            target_view,
+        }
+    }
+
+    fn make_target(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("FilterChain pass target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) {
+        let width = scaled_dimension(width, self.scale);
+        let height = scaled_dimension(height, self.scale);
+        self.target = Self::make_target(device, format, width, height);
+        self.target_view = self.target.create_view(&Default::default());
+    }
+
+    fn output_view(&self) -> &wgpu::TextureView {
+        &self.target_view
+    }
+
+    fn run(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        input: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+        frame_count: u32,
+        output_size: (u32, u32),
+        source_size: (u32, u32),
+    ) {
+        queue.write_buffer(
+            &self.uniform_buf,
+            0,
+            bytemuck::bytes_of(&FilterUniforms {
+                output_size: [output_size.0 as f32, output_size.1 as f32],
+                source_size: [source_size.0 as f32, source_size.1 as f32],
+                frame_count,
+                _padding: [0; 3],
+            }),
+        );
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("FilterChain pass bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(input),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniform_buf.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("FilterChain pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        // Full-screen triangle: the vertex shader derives position and UV
+        // from `@builtin(vertex_index)`, so no vertex buffer is bound.
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+fn scaled_dimension(base: u32, scale: f32) -> u32 {
+    ((base as f32) * scale).round().max(1.0) as u32
+}
+
+/// Renders the scene offscreen, then runs an ordered chain of full-screen
+/// shader passes over it before presenting. Acts as the terminal node of a
+/// [`crate::render_graph::RenderGraph`]: it reads the `scene_color` slot and
+/// writes the graph's final result straight into the swapchain.
+pub struct FilterChain {
+    surface_format: wgpu::TextureFormat,
+    passes: Vec<FilterPass>,
+    frame_count: u32,
+}
+
+impl FilterChain {
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        presets: &[FilterPresetPass],
+        shader_sources: &[String],
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let passes = presets
+            .iter()
+            .zip(shader_sources)
+            .map(|(preset, source)| {
+                FilterPass::new(device, surface_format, preset, source, width, height)
+            })
+            .collect();
+
+        Self {
+            surface_format,
+            passes,
+            frame_count: 0,
+        }
+    }
+}
+
+impl Pass for FilterChain {
+    fn name(&self) -> &str {
+        "filter_chain"
+    }
+
+    fn inputs(&self) -> &[&str] {
+        &[SCENE_COLOR_SLOT]
+    }
+
+    fn execute(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        inputs: &[&SlotTexture],
+        _outputs: &[&SlotTexture],
+        surface: &wgpu::TextureView,
+        _depth: Option<&DepthTarget<'_>>,
+    ) {
+        let scene = inputs[0];
+        let scene_size = (scene.texture.width(), scene.texture.height());
+
+        let mut previous_view: &wgpu::TextureView = &scene.view;
+        let mut previous_size = scene_size;
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            let (input, source_size) = match pass.source {
+                FilterSource::Original => (&scene.view, scene_size),
+                FilterSource::Previous => (previous_view, previous_size),
+            };
+            let is_last = index + 1 == self.passes.len();
+            let pass_size = (pass.target.width(), pass.target.height());
+            let (target, output_size) = if is_last {
+                (surface, scene_size)
+            } else {
+                (pass.output_view(), pass_size)
+            };
+
+            pass.run(
+                device,
+                queue,
+                encoder,
+                input,
+                target,
+                self.frame_count,
+                output_size,
+                source_size,
+            );
+
+            previous_view = pass.output_view();
+            previous_size = pass_size;
+        }
+
+        self.frame_count = self.frame_count.wrapping_add(1);
+    }
+
+    // `Pass::resize` doesn't receive a `&wgpu::Device`, so ping-pong buffers
+    // can't be reallocated from here. Call `FilterChain::resize_with_device`
+    // directly from `WindowEvent::Resized` instead.
+
+    fn as_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl FilterChain {
+    /// Recreates every pass's ping-pong buffers at the new surface size.
+    /// Call this from `WindowEvent::Resized` alongside the usual
+    /// `RenderGraph::passes_mut` resize sweep.
+    pub fn resize_with_device(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        for pass in self.passes.iter_mut() {
+            pass.resize(device, self.surface_format, width, height);
+        }
+    }
+}
@@ -1,22 +1,189 @@
+pub mod camera;
+pub mod compute;
+pub mod filter_chain;
+#[cfg(feature = "obj")]
+pub mod model;
 pub mod object;
+pub mod render_graph;
 
 pub use wgpu;
 pub use winit;
 
-use std::{error::Error, sync::Arc};
+use std::{any::Any, error::Error, sync::Arc};
 
 use winit::{
-    event::{Event, KeyEvent, WindowEvent},
+    event::{DeviceEvent, ElementState, Event, KeyEvent, MouseButton, WindowEvent},
     event_loop::{EventLoop, EventLoopWindowTarget},
     keyboard::{Key, NamedKey},
     window::Window,
 };
 
+use camera::{Camera, CameraController};
+use compute::Computable;
+use filter_chain::{FilterChain, SCENE_COLOR_SLOT};
+use render_graph::{DepthTarget, Pass, RenderGraph, SlotDescriptor, SlotTexture};
+
 pub trait Renderable {
     fn render<'a>(&'a mut self, rpass: &mut wgpu::RenderPass<'a>, queue: &wgpu::Queue);
     fn resize(&mut self, width: u32, height: u32, queue: &wgpu::Queue);
 }
 
+/// The implicit terminal node of every [`Graphics`]' render graph: it owns
+/// the flat list of [`Renderable`]s and draws them straight into the
+/// swapchain view, exactly like the single hard-coded pass this crate used
+/// to have. Once `Graphics::enable_filter_chain` is called it instead draws
+/// into the `scene_color` slot and the filter chain becomes the terminal
+/// node. Other passes (shadow maps, G-buffers, post-processing) can be
+/// added ahead of it via `Graphics::render_graph`.
+const SCENE_PASS_NAME: &str = "scene";
+
+/// Where `ScenePass` writes its renderables: straight to the swapchain (the
+/// default), or into the `scene_color` slot for a [`FilterChain`] to
+/// consume.
+enum SceneTarget {
+    Surface,
+    Offscreen(Vec<(&'static str, SlotDescriptor)>),
+}
+
+struct ScenePass {
+    renderables: Vec<Box<dyn Renderable>>,
+    target: SceneTarget,
+}
+
+impl Pass for ScenePass {
+    fn name(&self) -> &str {
+        SCENE_PASS_NAME
+    }
+
+    fn outputs(&self) -> &[(&str, SlotDescriptor)] {
+        match &self.target {
+            SceneTarget::Surface => &[],
+            SceneTarget::Offscreen(outputs) => outputs,
+        }
+    }
+
+    fn execute(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        _device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _inputs: &[&SlotTexture],
+        outputs: &[&SlotTexture],
+        surface: &wgpu::TextureView,
+        depth: Option<&DepthTarget<'_>>,
+    ) {
+        let view = match outputs.first() {
+            Some(scene_color) => &scene_color.view,
+            None => surface,
+        };
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: depth.map(|depth| wgpu::RenderPassDepthStencilAttachment {
+                view: depth.view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(depth.clear_depth),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        rpass.push_debug_group("Prepare data for draw.");
+        for renderable in self.renderables.iter_mut() {
+            renderable.render(&mut rpass, queue);
+        }
+    }
+
+    fn resize(&mut self, width: u32, height: u32, queue: &wgpu::Queue) {
+        for renderable in self.renderables.iter_mut() {
+            renderable.resize(width, height, queue);
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Depth-stencil settings for [`Graphics`]. Depth testing is opt-in: pass
+/// `GraphicsConfig::default()` (depth disabled) for a 2D or single-layer
+/// scene, or set `depth` for anything with overlapping 3D geometry.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GraphicsConfig {
+    pub depth: Option<DepthConfig>,
+}
+
+/// The format and clear value of `Graphics`' managed depth texture.
+#[derive(Clone, Copy, Debug)]
+pub struct DepthConfig {
+    pub format: wgpu::TextureFormat,
+    pub clear_depth: f32,
+}
+
+impl Default for DepthConfig {
+    fn default() -> Self {
+        Self {
+            format: wgpu::TextureFormat::Depth32Float,
+            clear_depth: 1.0,
+        }
+    }
+}
+
+struct DepthTexture {
+    config: DepthConfig,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl DepthTexture {
+    fn new(device: &wgpu::Device, config: DepthConfig, width: u32, height: u32) -> Self {
+        let texture = Self::make_texture(device, config.format, width, height);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self {
+            config,
+            texture,
+            view,
+        }
+    }
+
+    fn make_texture(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Graphics depth texture"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.texture = Self::make_texture(device, self.config.format, width, height);
+        self.view = self.texture.create_view(&wgpu::TextureViewDescriptor::default());
+    }
+}
+
 pub struct Graphics {
     pub window: Arc<Window>,
     pub instance: wgpu::Instance,
@@ -25,11 +192,21 @@ pub struct Graphics {
     pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub renderables: Vec<Box<dyn Renderable>>,
+    pub render_graph: RenderGraph,
+    /// Dispatched, in order, into the frame's `begin_compute_pass` ahead of
+    /// the render graph, so their output is visible to this frame's render.
+    pub computables: Vec<Box<dyn Computable>>,
+    depth_texture: Option<DepthTexture>,
+    camera: Option<Camera>,
+    camera_controller: Option<CameraController>,
+    camera_uniform_buf: Option<wgpu::Buffer>,
 }
 
 impl Graphics {
-    pub async fn new(window: Arc<Window>) -> Result<Self, Box<dyn Error>> {
+    pub async fn new(
+        window: Arc<Window>,
+        graphics_config: GraphicsConfig,
+    ) -> Result<Self, Box<dyn Error>> {
         let size = window.inner_size();
         let instance = wgpu::Instance::default();
         let surface = instance.create_surface(window.clone())?;
@@ -78,6 +255,16 @@ impl Graphics {
 
         surface.configure(&device, &config);
 
+        let mut render_graph = RenderGraph::new();
+        render_graph.add_pass(Box::new(ScenePass {
+            renderables: vec![],
+            target: SceneTarget::Surface,
+        }));
+
+        let depth_texture = graphics_config
+            .depth
+            .map(|depth_config| DepthTexture::new(&device, depth_config, size.width, size.height));
+
         Ok(Self {
             window,
             instance,
@@ -86,24 +273,106 @@ impl Graphics {
             adapter,
             device,
             queue,
-            renderables: vec![],
+            render_graph,
+            computables: vec![],
+            depth_texture,
+            camera: None,
+            camera_controller: None,
+            camera_uniform_buf: None,
         })
     }
 
+    /// Installs a free-fly camera driven by WASD + mouse-look, and allocates
+    /// the uniform buffer its view-projection matrix is written into every
+    /// frame. Bind `Graphics::camera_uniform_buffer` in a pipeline's bind
+    /// group to read the live camera instead of building a fixed matrix.
+    pub fn enable_camera(&mut self, camera: Camera, controller: CameraController) {
+        self.camera_uniform_buf = Some(self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Camera uniform buffer"),
+            size: std::mem::size_of::<[[f32; 4]; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        self.camera = Some(camera);
+        self.camera_controller = Some(controller);
+    }
+
+    pub fn camera_uniform_buffer(&self) -> Option<&wgpu::Buffer> {
+        self.camera_uniform_buf.as_ref()
+    }
+
+    /// The format of the managed depth texture, if depth testing is enabled,
+    /// for `Renderable`s to build a matching `wgpu::DepthStencilState` when
+    /// creating their pipelines.
+    pub fn depth_format(&self) -> Option<wgpu::TextureFormat> {
+        self.depth_texture.as_ref().map(|depth| depth.config.format)
+    }
+
+    /// Builds a `wgpu::DepthStencilState` for the managed depth texture with
+    /// the given comparison function, or `None` if depth testing is disabled.
+    pub fn depth_stencil_state(
+        &self,
+        compare: wgpu::CompareFunction,
+    ) -> Option<wgpu::DepthStencilState> {
+        self.depth_texture
+            .as_ref()
+            .map(|depth| wgpu::DepthStencilState {
+                format: depth.config.format,
+                depth_write_enabled: true,
+                depth_compare: compare,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            })
+    }
+
+    /// Appends a [`Renderable`] to the graph's built-in scene pass, the
+    /// terminal node that draws straight into the swapchain (or, after
+    /// `enable_filter_chain`, into the `scene_color` slot). Passes added
+    /// ahead of it via `self.render_graph.add_pass` run first each frame.
+    pub fn push_renderable(&mut self, renderable: Box<dyn Renderable>) {
+        let scene = self
+            .render_graph
+            .pass_mut(SCENE_PASS_NAME)
+            .and_then(|pass| pass.as_any().downcast_mut::<ScenePass>())
+            .expect("Graphics::new always installs the scene pass");
+        scene.renderables.push(renderable);
+    }
+
+    /// Reconfigures the scene pass to render into the `scene_color` slot
+    /// instead of the swapchain and installs `chain` as the new terminal
+    /// node, so every subsequent frame flows scene -> filter chain ->
+    /// swapchain.
+    pub fn enable_filter_chain(&mut self, chain: FilterChain) {
+        let scene = self
+            .render_graph
+            .pass_mut(SCENE_PASS_NAME)
+            .and_then(|pass| pass.as_any().downcast_mut::<ScenePass>())
+            .expect("Graphics::new always installs the scene pass");
+        scene.target = SceneTarget::Offscreen(vec![(
+            SCENE_COLOR_SLOT,
+            SlotDescriptor::new(
+                self.config.format,
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            ),
+        )]);
+        self.render_graph.add_pass(Box::new(chain));
+    }
+
     pub async fn run(mut self, event_loop: EventLoop<()>) {
+        let mut last_frame = std::time::Instant::now();
+
         event_loop
             .run(|event: Event<()>, target: &EventLoopWindowTarget<()>| {
                 let _ = (&self.instance, &self.adapter);
 
                 match event {
-                    Event::WindowEvent {
-                        event: WindowEvent::Resized(size),
+                    Event::DeviceEvent {
+                        event: DeviceEvent::MouseMotion { delta },
                         ..
                     } => {
-                        self.config.width = size.width;
-                        self.config.height = size.height;
-                        self.surface.configure(&self.device, &self.config);
-                        self.window.request_redraw();
+                        if let Some(controller) = self.camera_controller.as_mut() {
+                            controller.process_mouse(delta.0, delta.1);
+                        }
                     }
 
                     Event::WindowEvent { event, .. } => match event {
@@ -112,8 +381,15 @@ impl Graphics {
                             self.config.height = size.height.max(1);
                             self.surface.configure(&self.device, &self.config);
 
-                            for renderable in self.renderables.iter_mut() {
-                                renderable.resize(size.width, size.height, &self.queue);
+                            if let Some(depth_texture) = self.depth_texture.as_mut() {
+                                depth_texture.resize(&self.device, size.width, size.height);
+                            }
+
+                            for pass in self.render_graph.passes_mut() {
+                                pass.resize(size.width, size.height, &self.queue);
+                                if let Some(chain) = pass.as_any().downcast_mut::<FilterChain>() {
+                                    chain.resize_with_device(&self.device, size.width, size.height);
+                                }
                             }
 
                             self.window.request_redraw();
@@ -129,8 +405,63 @@ impl Graphics {
                         | WindowEvent::CloseRequested => {
                             target.exit();
                         }
+                        WindowEvent::KeyboardInput {
+                            event:
+                                KeyEvent {
+                                    logical_key,
+                                    state,
+                                    repeat: false,
+                                    ..
+                                },
+                            ..
+                        } => {
+                            if let Some(controller) = self.camera_controller.as_mut() {
+                                controller.process_keyboard(&logical_key, state);
+                            }
+                        }
+                        WindowEvent::MouseWheel { delta, .. } => {
+                            if let Some(controller) = self.camera_controller.as_mut() {
+                                controller.process_scroll(&delta);
+                            }
+                        }
+                        WindowEvent::MouseInput {
+                            state: ElementState::Pressed,
+                            button: MouseButton::Right,
+                            ..
+                        } => {
+                            if let Some(controller) = self.camera_controller.as_mut() {
+                                controller.capture_cursor = !controller.capture_cursor;
+                                let _ = self.window.set_cursor_grab(if controller.capture_cursor {
+                                    winit::window::CursorGrabMode::Confined
+                                } else {
+                                    winit::window::CursorGrabMode::None
+                                });
+                                self.window.set_cursor_visible(!controller.capture_cursor);
+                            }
+                        }
 
                         WindowEvent::RedrawRequested => {
+                            let now = std::time::Instant::now();
+                            let dt = (now - last_frame).as_secs_f32();
+                            last_frame = now;
+
+                            if let (Some(camera), Some(controller)) =
+                                (self.camera.as_mut(), self.camera_controller.as_mut())
+                            {
+                                controller.update_camera(camera, dt);
+                                let aspect_ratio =
+                                    self.config.width as f32 / self.config.height as f32;
+                                let view_projection = camera.view_projection(aspect_ratio);
+                                let matrix_ref: &[[f32; 4]; 4] = view_projection.as_ref();
+                                self.queue.write_buffer(
+                                    self.camera_uniform_buf.as_ref().expect(
+                                        "enable_camera always allocates the uniform buffer",
+                                    ),
+                                    0,
+                                    bytemuck::cast_slice(matrix_ref),
+                                );
+                            }
+
                             let frame = self
                                 .surface
                                 .get_current_texture()
@@ -141,30 +472,38 @@ impl Graphics {
                             let mut encoder = self.device.create_command_encoder(
                                 &wgpu::CommandEncoderDescriptor { label: None },
                             );
-                            {
-                                let mut rpass =
-                                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                                        label: None,
-                                        color_attachments: &[Some(
-                                            wgpu::RenderPassColorAttachment {
-                                                view: &view,
-                                                resolve_target: None,
-                                                ops: wgpu::Operations {
-                                                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                                                    store: wgpu::StoreOp::Store,
-                                                },
-                                            },
-                                        )],
-                                        depth_stencil_attachment: None,
+
+                            if !self.computables.is_empty() {
+                                let mut cpass =
+                                    encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                                        label: Some("Compute pass"),
                                         timestamp_writes: None,
-                                        occlusion_query_set: None,
                                     });
-                                rpass.push_debug_group("Prepare data for draw.");
-                                for renderable in self.renderables.iter_mut() {
-                                    renderable.render(&mut rpass, &self.queue);
+                                for computable in self.computables.iter_mut() {
+                                    computable.compute(&mut cpass, &self.queue);
                                 }
                             }
 
+                            let depth =
+                                self.depth_texture
+                                    .as_ref()
+                                    .map(|depth_texture| DepthTarget {
+                                        view: &depth_texture.view,
+                                        clear_depth: depth_texture.config.clear_depth,
+                                    });
+
+                            self.render_graph
+                                .execute(
+                                    &self.device,
+                                    &self.queue,
+                                    &mut encoder,
+                                    &view,
+                                    depth.as_ref(),
+                                    self.config.width,
+                                    self.config.height,
+                                )
+                                .expect("render graph has a cycle or an unresolved input slot");
+
                             self.queue.submit(Some(encoder.finish()));
                             frame.present();
                             self.window.request_redraw();
@@ -0,0 +1,255 @@
+//! Wavefront `.obj`/`.mtl` loading, behind the `obj` feature flag.
+//!
+//! Parses one or more submeshes out of an `.obj` file into the crate's
+//! existing `Vertex` layout and loads each referenced material's diffuse map
+//! into a `wgpu::Texture`, so a `Renderable` can bind the right texture per
+//! submesh instead of hand-writing vertex arrays and procedural textures.
+//!
+//! This module needs `tobj` (unconditional) and `image` (for the `to_rgba8`
+//! decode in `load_material`) declared as optional dependencies of this
+//! crate's `Cargo.toml`, pulled in by the `obj` feature:
+//!
+//! ```text
+//! [dependencies]
+//! tobj = { version = "4", optional = true }
+//! image = { version = "0.24", optional = true, default-features = false, features = ["png", "jpeg"] }
+//!
+//! [features]
+//! obj = ["dep:tobj", "dep:image"]
+//! ```
+//!
+//! No `Cargo.toml` exists anywhere in this tree to carry that change, so it
+//! isn't reflected in a manifest here — add it to whichever manifest this
+//! crate is vendored into.
+
+use std::{error::Error, fmt, ops::Range, path::Path};
+
+use wgpu::util::DeviceExt;
+
+use crate::object::Vertex;
+
+/// A contiguous run of indices in `Model::index_buf` that should be drawn
+/// with `Model::materials[material]`, or left unbound (`None`) for a submesh
+/// whose `.obj` references no material, or a material id `.mtl` didn't
+/// define.
+pub struct Submesh {
+    pub index_range: Range<u32>,
+    pub material: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum ModelError {
+    /// A submesh's cumulative vertex count overflowed the `u16` indices this
+    /// crate's `Vertex` layout uses; split the mesh or switch to `u32`
+    /// indices before loading it.
+    TooManyVertices { submesh: usize },
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::TooManyVertices { submesh } => write!(
+                f,
+                "submesh {submesh} has more than 65536 cumulative vertices, which overflows u16 indices"
+            ),
+        }
+    }
+}
+
+impl Error for ModelError {}
+
+/// A loaded material's diffuse map, ready to bind at render time.
+pub struct Material {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// The result of loading an `.obj`/`.mtl` pair: vertex/index buffers shared
+/// across every submesh, the submesh ranges, and the materials they index
+/// into.
+pub struct Model {
+    pub vertex_buf: wgpu::Buffer,
+    pub index_buf: wgpu::Buffer,
+    pub index_count: usize,
+    pub submeshes: Vec<Submesh>,
+    pub materials: Vec<Material>,
+}
+
+/// Loads `path` (and its sibling `.mtl` file(s)) into a [`Model`], creating
+/// one `wgpu::Texture` per material using `bind_group_layout`, which must
+/// hold a filterable texture view at binding 0 and a filtering sampler at
+/// binding 1.
+pub fn load(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    path: impl AsRef<Path>,
+) -> Result<Model, Box<dyn Error>> {
+    let path = path.as_ref();
+    let (obj_models, obj_materials) = tobj::load_obj(
+        path,
+        &tobj::LoadOptions {
+            triangulate: true,
+            single_index: true,
+            ..Default::default()
+        },
+    )?;
+    let obj_materials = obj_materials?;
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
+    let mut submeshes = Vec::with_capacity(obj_models.len());
+
+    for (submesh_index, obj_model) in obj_models.iter().enumerate() {
+        let mesh = &obj_model.mesh;
+        let base_vertex =
+            u16::try_from(vertices.len()).map_err(|_| ModelError::TooManyVertices {
+                submesh: submesh_index,
+            })?;
+
+        let vertex_count = mesh.positions.len() / 3;
+        for i in 0..vertex_count {
+            let tex_coord = if mesh.texcoords.len() >= (i + 1) * 2 {
+                [mesh.texcoords[i * 2], mesh.texcoords[i * 2 + 1]]
+            } else {
+                [0.0, 0.0]
+            };
+            vertices.push(Vertex {
+                _pos: [
+                    mesh.positions[i * 3],
+                    mesh.positions[i * 3 + 1],
+                    mesh.positions[i * 3 + 2],
+                    1.0,
+                ],
+                _tex_coord: tex_coord,
+            });
+        }
+
+        let start = indices.len() as u32;
+        for index in &mesh.indices {
+            let index = u16::try_from(*index)
+                .ok()
+                .and_then(|index| base_vertex.checked_add(index))
+                .ok_or(ModelError::TooManyVertices {
+                    submesh: submesh_index,
+                })?;
+            indices.push(index);
+        }
+
+        // `material_id` is only a valid index into `obj_materials`; an
+        // untextured `.obj` with no `.mtl` leaves it `None`, and a `.obj`
+        // naming a material its `.mtl` doesn't define leaves `obj_materials`
+        // too short to index. Either way, leave the submesh unbound rather
+        // than defaulting to a material index that may not exist.
+        let material = mesh
+            .material_id
+            .filter(|&material_id| material_id < obj_materials.len());
+
+        submeshes.push(Submesh {
+            index_range: start..indices.len() as u32,
+            material,
+        });
+    }
+
+    let vertex_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Model Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Model Index Buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let materials = obj_materials
+        .iter()
+        .map(|material| load_material(device, queue, bind_group_layout, base_dir, material))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    Ok(Model {
+        vertex_buf,
+        index_buf,
+        index_count: indices.len(),
+        submeshes,
+        materials,
+    })
+}
+
+fn load_material(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    base_dir: &Path,
+    material: &tobj::Material,
+) -> Result<Material, Box<dyn Error>> {
+    let diffuse_path = material
+        .diffuse_texture
+        .as_ref()
+        .map(|texture| base_dir.join(texture));
+
+    let image = match diffuse_path {
+        Some(path) => image::open(path)?.to_rgba8(),
+        None => image::RgbaImage::from_pixel(1, 1, image::Rgba([255, 255, 255, 255])),
+    };
+    let (width, height) = image.dimensions();
+
+    let texture_extent = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Model diffuse texture"),
+        size: texture_extent,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        texture.as_image_copy(),
+        &image,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * width),
+            rows_per_image: Some(height),
+        },
+        texture_extent,
+    );
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Model diffuse sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Model material bind group"),
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&sampler),
+            },
+        ],
+    });
+
+    Ok(Material {
+        texture,
+        view,
+        sampler,
+        bind_group,
+    })
+}
@@ -1,4 +1,5 @@
 use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -17,3 +18,163 @@ impl Object {
         Self { vertices, indices }
     }
 }
+
+/// Per-instance data uploaded alongside an `Object`'s vertex buffer in a
+/// second `wgpu::Buffer` with `step_mode: VertexStepMode::Instance`. Layer
+/// this on top of the existing per-vertex `Vertex` buffer layout at the next
+/// free `shader_location`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Instance {
+    pub model: [[f32; 4]; 4],
+    pub color: [f32; 4],
+}
+
+impl Instance {
+    pub const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        2 => Float32x4,
+        3 => Float32x4,
+        4 => Float32x4,
+        5 => Float32x4,
+        6 => Float32x4,
+    ];
+
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Owns the GPU-side buffer backing a draw's per-instance transforms,
+/// growing it (and re-uploading everything) whenever the instance count
+/// outgrows the current capacity, and otherwise just rewriting the live
+/// buffer contents in place.
+pub struct Instances {
+    buffer: wgpu::Buffer,
+    capacity: usize,
+    len: usize,
+}
+
+impl Instances {
+    pub fn new(device: &wgpu::Device, instances: &[Instance]) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(instances),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            buffer,
+            capacity: instances.len(),
+            len: instances.len(),
+        }
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Rewrites the instance data for the next draw. Grows (and
+    /// reallocates) the buffer when `instances` no longer fits in the
+    /// current capacity; otherwise just rewrites the existing buffer.
+    pub fn set(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, instances: &[Instance]) {
+        if instances.len() > self.capacity {
+            self.buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Instance Buffer"),
+                contents: bytemuck::cast_slice(instances),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+            self.capacity = instances.len();
+        } else {
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(instances));
+        }
+        self.len = instances.len();
+    }
+}
+
+// These tests create a real `wgpu::Device` (falling back to a software
+// adapter so they run headless), which needs `pollster` as a dev-dependency
+// of this crate's `Cargo.toml` — see `crate::model`'s module docs for why no
+// manifest change accompanies this.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_device() -> (wgpu::Device, wgpu::Queue) {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: true,
+            compatible_surface: None,
+        }))
+        .expect("test harness always has at least a software adapter");
+
+        pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::downlevel_webgl2_defaults(),
+            },
+            None,
+        ))
+        .expect("failed to create a test device")
+    }
+
+    fn instance(shade: f32) -> Instance {
+        Instance {
+            model: [[0.0; 4]; 4],
+            color: [shade, shade, shade, 1.0],
+        }
+    }
+
+    #[test]
+    fn set_rewrites_in_place_when_capacity_suffices() {
+        let (device, queue) = test_device();
+        let mut instances = Instances::new(&device, &[instance(1.0), instance(2.0)]);
+        assert_eq!(instances.capacity, 2);
+
+        instances.set(&device, &queue, &[instance(3.0)]);
+
+        assert_eq!(instances.len(), 1);
+        assert!(!instances.is_empty());
+        assert_eq!(
+            instances.capacity, 2,
+            "rewriting fewer instances than capacity shouldn't reallocate"
+        );
+    }
+
+    #[test]
+    fn set_reallocates_when_it_outgrows_capacity() {
+        let (device, queue) = test_device();
+        let mut instances = Instances::new(&device, &[instance(1.0)]);
+        assert_eq!(instances.capacity, 1);
+
+        instances.set(
+            &device,
+            &queue,
+            &[instance(1.0), instance(2.0), instance(3.0)],
+        );
+
+        assert_eq!(instances.len(), 3);
+        assert_eq!(instances.capacity, 3);
+    }
+
+    #[test]
+    fn new_with_no_instances_is_empty() {
+        let (device, _queue) = test_device();
+        let instances = Instances::new(&device, &[]);
+        assert!(instances.is_empty());
+        assert_eq!(instances.len(), 0);
+    }
+}
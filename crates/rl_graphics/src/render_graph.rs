@@ -0,0 +1,402 @@
+use std::{collections::HashMap, fmt};
+
+/// Description of a texture produced by a [`Pass`] as one of its output slots.
+///
+/// `width`/`height` of `None` means "match the current swapchain size"; the
+/// graph re-derives the concrete size each time it (re)allocates the slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotDescriptor {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: wgpu::TextureFormat,
+    pub usage: wgpu::TextureUsages,
+}
+
+impl SlotDescriptor {
+    pub fn new(format: wgpu::TextureFormat, usage: wgpu::TextureUsages) -> Self {
+        Self {
+            width: None,
+            height: None,
+            format,
+            usage,
+        }
+    }
+}
+
+/// A resolved render target handed to a [`Pass`] while it records commands.
+pub struct SlotTexture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+}
+
+/// The depth-stencil attachment shared by every pass in a frame, owned by
+/// [`crate::Graphics`] rather than by any one slot, since most scenes want a
+/// single depth buffer behind every pass that draws 3D geometry.
+pub struct DepthTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub clear_depth: f32,
+}
+
+/// A single node in the [`RenderGraph`].
+///
+/// A pass declares the named slots it reads (`inputs`) and the named slots it
+/// produces (`outputs`). The graph wires an edge from pass `A` to pass `B`
+/// whenever `B` lists one of `A`'s outputs as an input, then records passes
+/// in the resulting topological order.
+pub trait Pass {
+    fn name(&self) -> &str;
+
+    fn inputs(&self) -> &[&str] {
+        &[]
+    }
+
+    fn outputs(&self) -> &[(&str, SlotDescriptor)] {
+        &[]
+    }
+
+    /// Record this pass's commands into `encoder`. `inputs` holds the
+    /// resolved textures for each name returned from [`Pass::inputs`], and
+    /// `outputs` holds the resolved textures for each name returned from
+    /// [`Pass::outputs`], in the same order. `surface` is the swapchain view
+    /// for the frame, available to the final pass in the chain. `depth` is
+    /// `Graphics`' shared depth-stencil attachment, if depth testing is
+    /// enabled.
+    fn execute(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        inputs: &[&SlotTexture],
+        outputs: &[&SlotTexture],
+        surface: &wgpu::TextureView,
+        depth: Option<&DepthTarget<'_>>,
+    );
+
+    /// Called when the surface is resized, before the next `execute`.
+    fn resize(&mut self, _width: u32, _height: u32, _queue: &wgpu::Queue) {}
+
+    /// Enables downcasting back to the concrete pass type, e.g. so callers
+    /// can reach into a known pass (like `Graphics`' built-in scene pass)
+    /// after it has been added to the graph.
+    fn as_any(&mut self) -> &mut dyn std::any::Any;
+}
+
+#[derive(Debug)]
+pub enum RenderGraphError {
+    Cycle,
+    UnknownInput { pass: String, slot: String },
+}
+
+impl fmt::Display for RenderGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderGraphError::Cycle => write!(f, "render graph contains a dependency cycle"),
+            RenderGraphError::UnknownInput { pass, slot } => write!(
+                f,
+                "pass '{pass}' declares input slot '{slot}' which no pass produces"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RenderGraphError {}
+
+/// Owns the named [`Pass`] nodes that make up a frame and the intermediate
+/// textures flowing between them.
+///
+/// Each frame, `RenderGraph::execute` builds a dependency edge whenever one
+/// pass's input slot names another pass's output slot, topologically sorts
+/// the passes (Kahn's algorithm, erroring on cycles), allocates or reuses the
+/// textures described by each output slot, and then records one pass per
+/// node in dependency order.
+pub struct RenderGraph {
+    passes: Vec<Box<dyn Pass>>,
+    textures: HashMap<String, SlotTexture>,
+}
+
+impl Default for RenderGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self {
+            passes: Vec::new(),
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn add_pass(&mut self, pass: Box<dyn Pass>) {
+        self.passes.push(pass);
+    }
+
+    pub fn pass_mut(&mut self, name: &str) -> Option<&mut dyn Pass> {
+        self.passes
+            .iter_mut()
+            .find(|pass| pass.name() == name)
+            .map(|pass| pass.as_mut())
+    }
+
+    pub fn passes_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn Pass>> {
+        self.passes.iter_mut()
+    }
+
+    /// Kahn's algorithm over the slot dependency map: an edge runs from the
+    /// pass producing a slot to every pass consuming it.
+    fn topological_order(&self) -> Result<Vec<usize>, RenderGraphError> {
+        let mut producer_of: HashMap<&str, usize> = HashMap::new();
+        for (index, pass) in self.passes.iter().enumerate() {
+            for (slot, _) in pass.outputs() {
+                producer_of.insert(slot, index);
+            }
+        }
+
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.passes.len()];
+        let mut in_degree: Vec<usize> = vec![0; self.passes.len()];
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            for slot in pass.inputs() {
+                match producer_of.get(slot) {
+                    Some(&producer) => {
+                        dependents[producer].push(index);
+                        in_degree[index] += 1;
+                    }
+                    None => {
+                        return Err(RenderGraphError::UnknownInput {
+                            pass: pass.name().to_string(),
+                            slot: slot.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut ready: Vec<usize> = in_degree
+            .iter()
+            .enumerate()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(index, _)| index)
+            .collect();
+        let mut order = Vec::with_capacity(self.passes.len());
+
+        while let Some(node) = ready.pop() {
+            order.push(node);
+            for &dependent in &dependents[node] {
+                in_degree[dependent] -= 1;
+                if in_degree[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+
+        if order.len() != self.passes.len() {
+            return Err(RenderGraphError::Cycle);
+        }
+
+        Ok(order)
+    }
+
+    fn resolve_size(descriptor: &SlotDescriptor, width: u32, height: u32) -> (u32, u32) {
+        (
+            descriptor.width.unwrap_or(width),
+            descriptor.height.unwrap_or(height),
+        )
+    }
+
+    fn ensure_texture(
+        &mut self,
+        device: &wgpu::Device,
+        name: &str,
+        descriptor: &SlotDescriptor,
+        width: u32,
+        height: u32,
+    ) {
+        let (width, height) = Self::resolve_size(descriptor, width, height);
+        let needs_alloc = match self.textures.get(name) {
+            Some(existing) => {
+                let size = existing.texture.size();
+                size.width != width
+                    || size.height != height
+                    || existing.texture.format() != descriptor.format
+            }
+            None => true,
+        };
+
+        if needs_alloc {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(name),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: descriptor.format,
+                usage: descriptor.usage,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.textures
+                .insert(name.to_string(), SlotTexture { texture, view });
+        }
+    }
+
+    /// Run the graph for one frame, recording every pass into `encoder` in
+    /// dependency order. The final pass in the order is expected to write
+    /// into `surface`.
+    pub fn execute(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        surface: &wgpu::TextureView,
+        depth: Option<&DepthTarget<'_>>,
+        width: u32,
+        height: u32,
+    ) -> Result<(), RenderGraphError> {
+        let order = self.topological_order()?;
+
+        for index in order {
+            let descriptors: Vec<(String, SlotDescriptor)> = self.passes[index]
+                .outputs()
+                .iter()
+                .map(|(slot, descriptor)| (slot.to_string(), descriptor.clone()))
+                .collect();
+            for (slot, descriptor) in &descriptors {
+                self.ensure_texture(device, slot, descriptor, width, height);
+            }
+
+            let input_names: Vec<String> = self.passes[index]
+                .inputs()
+                .iter()
+                .map(|slot| slot.to_string())
+                .collect();
+            let inputs: Vec<&SlotTexture> = input_names
+                .iter()
+                .map(|slot| self.textures.get(slot).expect("input slot was produced"))
+                .collect();
+            let outputs: Vec<&SlotTexture> = descriptors
+                .iter()
+                .map(|(slot, _)| self.textures.get(slot).expect("output slot was allocated"))
+                .collect();
+
+            self.passes[index].execute(encoder, device, queue, &inputs, &outputs, surface, depth);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubPass {
+        name: &'static str,
+        inputs: Vec<&'static str>,
+        outputs: Vec<(&'static str, SlotDescriptor)>,
+    }
+
+    impl Pass for StubPass {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn inputs(&self) -> &[&str] {
+            &self.inputs
+        }
+
+        fn outputs(&self) -> &[(&str, SlotDescriptor)] {
+            &self.outputs
+        }
+
+        fn execute(
+            &mut self,
+            _encoder: &mut wgpu::CommandEncoder,
+            _device: &wgpu::Device,
+            _queue: &wgpu::Queue,
+            _inputs: &[&SlotTexture],
+            _outputs: &[&SlotTexture],
+            _surface: &wgpu::TextureView,
+            _depth: Option<&DepthTarget<'_>>,
+        ) {
+        }
+
+        fn as_any(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+    }
+
+    fn slot() -> SlotDescriptor {
+        SlotDescriptor::new(
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        )
+    }
+
+    #[test]
+    fn topological_order_resolves_chain() {
+        let mut graph = RenderGraph::new();
+        graph.add_pass(Box::new(StubPass {
+            name: "a",
+            inputs: vec![],
+            outputs: vec![("a_out", slot())],
+        }));
+        graph.add_pass(Box::new(StubPass {
+            name: "b",
+            inputs: vec!["a_out"],
+            outputs: vec![],
+        }));
+
+        let order = graph.topological_order().expect("acyclic graph resolves");
+        let position = |name: &str| {
+            order
+                .iter()
+                .position(|&index| graph.passes[index].name() == name)
+                .unwrap()
+        };
+        assert!(position("a") < position("b"));
+    }
+
+    #[test]
+    fn topological_order_detects_cycle() {
+        let mut graph = RenderGraph::new();
+        graph.add_pass(Box::new(StubPass {
+            name: "a",
+            inputs: vec!["b_out"],
+            outputs: vec![("a_out", slot())],
+        }));
+        graph.add_pass(Box::new(StubPass {
+            name: "b",
+            inputs: vec!["a_out"],
+            outputs: vec![("b_out", slot())],
+        }));
+
+        assert!(matches!(
+            graph.topological_order(),
+            Err(RenderGraphError::Cycle)
+        ));
+    }
+
+    #[test]
+    fn topological_order_errors_on_unknown_input() {
+        let mut graph = RenderGraph::new();
+        graph.add_pass(Box::new(StubPass {
+            name: "a",
+            inputs: vec!["missing"],
+            outputs: vec![],
+        }));
+
+        match graph.topological_order() {
+            Err(RenderGraphError::UnknownInput { pass, slot }) => {
+                assert_eq!(pass, "a");
+                assert_eq!(slot, "missing");
+            }
+            other => panic!("expected UnknownInput, got {other:?}"),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
+use rl_graphics::camera::{Camera, CameraController};
 use rl_graphics::winit::event_loop::EventLoop;
-use rl_graphics::Graphics;
+use rl_graphics::{DepthConfig, Graphics, GraphicsConfig};
 
 use game::Cube;
 
@@ -17,11 +18,23 @@ pub fn main() {
     let event_loop = EventLoop::new().unwrap();
     let builder = rl_graphics::winit::window::WindowBuilder::new();
     let window = Arc::new(builder.build(&event_loop).unwrap());
-    let mut graphics = pollster::block_on(Graphics::new(window)).unwrap();
+    let graphics_config = GraphicsConfig {
+        depth: Some(DepthConfig::default()),
+    };
+    let mut graphics = pollster::block_on(Graphics::new(window, graphics_config)).unwrap();
+
+    // Yaw/pitch approximating the old fixed eye (1.5, -5.0, 3.0) looking at
+    // the origin, so the live camera starts from the same viewpoint. Its
+    // projection uses `Camera`'s own fov and clip planes, not those of
+    // `generate_matrix`.
+    graphics.enable_camera(
+        Camera::new(nalgebra::Point3::new(1.5, -5.0, 3.0), 1.8622, -0.5224),
+        CameraController::new(4.0, 0.4),
+    );
 
     let cube = Cube::new(&graphics);
 
-    graphics.renderables.push(Box::new(cube));
+    graphics.push_renderable(Box::new(cube));
 
     pollster::block_on(graphics.run(event_loop));
 }
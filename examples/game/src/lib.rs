@@ -1,3 +1,9 @@
+// `pipeline.rs` and `vertices.rs` are not present in this source snapshot
+// (the `create_pipeline`/`create_vertices` they declare are referenced
+// below but cannot be compiled here). `create_pipeline`'s signature below
+// has still been updated for depth testing — it now takes the optional
+// `wgpu::DepthStencilState` from `Graphics::depth_stencil_state` and must
+// set it on its `RenderPipelineDescriptor`.
 mod pipeline;
 mod vertices;
 
@@ -10,7 +16,11 @@ use {pipeline::create_pipeline, vertices::create_vertices};
 pub struct Cube {
     vertex_buf: wgpu::Buffer,
     index_buf: wgpu::Buffer,
-    uniform_buf: wgpu::Buffer,
+    /// Only set when no live camera is enabled on `Graphics`; in that case
+    /// this buffer holds the fixed matrix from `generate_matrix` and is
+    /// rewritten on resize. When a camera is enabled, `Cube` binds straight
+    /// to `Graphics::camera_uniform_buffer` instead and this stays `None`.
+    uniform_buf: Option<wgpu::Buffer>,
     render_pipeline: wgpu::RenderPipeline,
     bind_group: wgpu::BindGroup,
     index_count: usize,
@@ -65,16 +75,29 @@ impl Cube {
             texture_extent,
         );
 
-        let mx_total =
-            generate_matrix(graphics.config.width as f32 / graphics.config.height as f32);
-        let mx_ref: &[[f32; 4]; 4] = mx_total.as_ref();
-        let uniform_buf = graphics
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Uniform Buffer"),
-                contents: bytemuck::cast_slice(mx_ref),
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            });
+        // Prefer the live camera's uniform buffer when `Graphics::enable_camera`
+        // has been called; otherwise fall back to a fixed matrix, as before.
+        let uniform_buf = match graphics.camera_uniform_buffer() {
+            Some(_) => None,
+            None => {
+                let mx_total =
+                    generate_matrix(graphics.config.width as f32 / graphics.config.height as f32);
+                let mx_ref: &[[f32; 4]; 4] = mx_total.as_ref();
+                Some(
+                    graphics
+                        .device
+                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                            label: Some("Uniform Buffer"),
+                            contents: bytemuck::cast_slice(mx_ref),
+                            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                        }),
+                )
+            }
+        };
+        let camera_buf = uniform_buf
+            .as_ref()
+            .or(graphics.camera_uniform_buffer())
+            .expect("either a fixed uniform buffer or a live camera buffer is always set");
 
         let vertex_size = mem::size_of::<Vertex>();
         let vertex_buffers = [wgpu::VertexBufferLayout {
@@ -97,8 +120,9 @@ impl Cube {
         let (render_pipeline, bind_group) = create_pipeline(
             graphics,
             vertex_buffers.as_slice(),
-            &uniform_buf,
+            camera_buf,
             &texture_view,
+            graphics.depth_stencil_state(wgpu::CompareFunction::Less),
         );
 
         let index_count = object.indices.len();
@@ -125,9 +149,11 @@ impl Renderable for Cube {
     }
 
     fn resize(&mut self, width: u32, height: u32, queue: &wgpu::Queue) {
-        let mx_total = generate_matrix(width as f32 / height as f32);
-        let mx_ref: &[[f32; 4]; 4] = mx_total.as_ref();
-        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(mx_ref));
+        if let Some(uniform_buf) = &self.uniform_buf {
+            let mx_total = generate_matrix(width as f32 / height as f32);
+            let mx_ref: &[[f32; 4]; 4] = mx_total.as_ref();
+            queue.write_buffer(uniform_buf, 0, bytemuck::cast_slice(mx_ref));
+        }
     }
 }
 